@@ -7,14 +7,177 @@ use super::{
     value::{Array, DateTime, InlineTable, Value},
 };
 
+/// Writes a node's textual representation to `buf`.
+///
+/// Every node prefers its own captured decor (leading/trailing whitespace,
+/// comments, blank lines) when it has any, and otherwise falls back to
+/// `default_decor`. This keeps output well-formed even for nodes that were
+/// synthesized or relocated (for example when sorting moves a key out of
+/// the position its original decor was captured for) instead of silently
+/// dropping the spaces or newlines that decor would normally supply.
+///
+/// When `canonicalize` is set, nodes that support it rewrite their repr
+/// from the parsed value instead of echoing the raw text verbatim (see
+/// `escape_string` and `canonical_float`).
+///
+/// `separator`, when set, is written right after the node's content but
+/// before its suffix decor. This lets a container inject a trailing `,`
+/// after its last element without cloning the element to splice the comma
+/// into its decor.
+///
+/// `layout` controls how arrays and inline tables lay out their elements;
+/// nodes that don't have elements of their own just ignore it.
+trait Encode {
+    fn encode(
+        &self,
+        buf: &mut dyn Write,
+        default_decor: (&str, &str),
+        canonicalize: bool,
+        separator: Option<&str>,
+        layout: Layout,
+    ) -> Result;
+}
+
+/// Controls how [`Array`] and [`InlineTable`] lay out their elements,
+/// independent of whatever layout the source document used.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    /// Faithfully reproduce the original newlines, trailing comma, and
+    /// per-element decor.
+    Preserve,
+    /// Force a single line: `", "` between elements, no trailing comma.
+    Compact,
+    /// One element per line, indented by `indent` spaces, with a trailing
+    /// comma on the last element.
+    Expanded { indent: usize },
+}
+
+impl Default for Layout {
+    fn default() -> Self { Layout::Preserve }
+}
+
+/// Writes `value`'s content only, without its own decor, for use by
+/// [`Layout::Compact`]/[`Layout::Expanded`] where the element's captured
+/// whitespace is being discarded rather than preserved.
+fn encode_value_content(value: &Value, buf: &mut dyn Write, canonicalize: bool, layout: Layout) -> Result {
+    match value {
+        Value::Integer(repr) => write!(buf, "{}", repr.repr.raw_value),
+        Value::Boolean(repr) => write!(buf, "{}", repr.repr.raw_value),
+        Value::DateTime(repr) => write!(buf, "{}", repr.repr.raw_value),
+        Value::String(repr) if canonicalize => write!(buf, "{}", escape_string(&repr.value)),
+        Value::String(repr) => write!(buf, "{}", repr.repr.raw_value),
+        Value::Float(repr) if canonicalize => {
+            write!(buf, "{}", canonical_float(&repr.repr.raw_value, repr.value))
+        }
+        Value::Float(repr) => write!(buf, "{}", repr.repr.raw_value),
+        // Nested containers honor the same layout as their parent, rendered
+        // bare (no decor of their own) so Compact/Expanded apply uniformly
+        // instead of stopping at the first level or reintroducing the
+        // source's whitespace one level down.
+        Value::Array(array) => array.encode_body(buf, canonicalize, layout),
+        Value::InlineTable(table) => table.encode_body(buf, canonicalize, layout),
+    }
+}
+
+/// Returns `decor` unless it's empty, in which case `default` is used.
+fn decor_or<'a>(decor: &'a str, default: &'a str) -> &'a str {
+    if decor.is_empty() {
+        default
+    } else {
+        decor
+    }
+}
+
+/// Escapes `s` as a basic TOML string, ignoring whatever quoting the
+/// original repr used.
+fn escape_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '\u{8}' => out.push_str("\\b"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\u{c}' => out.push_str("\\f"),
+            '\r' => out.push_str("\\r"),
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 || c as u32 == 0x7F => {
+                write!(out, "\\u{:04X}", c as u32).unwrap();
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Appends `.0` to `raw` when `value` is a whole number and `raw` doesn't
+/// already look like a float, so it keeps round-tripping as one.
+fn canonical_float(raw: &str, value: f64) -> String {
+    let looks_like_float = raw.contains('.')
+        || raw.contains('e')
+        || raw.contains('E')
+        || raw.contains("inf")
+        || raw.contains("nan");
+    if value % 1.0 == 0.0 && !looks_like_float {
+        format!("{}.0", raw)
+    } else {
+        raw.to_string()
+    }
+}
+
+impl Repr {
+    /// Encodes `text` using this repr's decor (or `default_decor` when it
+    /// has none), in place of `raw_value`, with an optional separator
+    /// written between the text and the suffix.
+    fn encode_as(
+        &self,
+        buf: &mut dyn Write,
+        default_decor: (&str, &str),
+        text: &str,
+        separator: Option<&str>,
+    ) -> Result {
+        write!(buf, "{}{}", decor_or(&self.decor.prefix, default_decor.0), text)?;
+        if let Some(sep) = separator {
+            write!(buf, "{}", sep)?;
+        }
+        write!(buf, "{}", decor_or(&self.decor.suffix, default_decor.1))
+    }
+}
+
+impl Encode for Repr {
+    fn encode(
+        &self,
+        buf: &mut dyn Write,
+        default_decor: (&str, &str),
+        _canonicalize: bool,
+        separator: Option<&str>,
+        _layout: Layout,
+    ) -> Result {
+        self.encode_as(buf, default_decor, &self.raw_value, separator)
+    }
+}
+
 impl Display for Repr {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "{}{}{}", self.decor.prefix, self.raw_value, self.decor.suffix)
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result { self.encode(f, ("", ""), false, None, Layout::Preserve) }
+}
+
+impl<T> Encode for Formatted<T> {
+    fn encode(
+        &self,
+        buf: &mut dyn Write,
+        default_decor: (&str, &str),
+        canonicalize: bool,
+        separator: Option<&str>,
+        layout: Layout,
+    ) -> Result {
+        self.repr.encode(buf, default_decor, canonicalize, separator, layout)
     }
 }
 
 impl<T> Display for Formatted<T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result { write!(f, "{}", self.repr) }
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result { self.encode(f, ("", ""), false, None, Layout::Preserve) }
 }
 
 impl Display for DateTime {
@@ -28,71 +191,201 @@ impl Display for DateTime {
     }
 }
 
-impl Display for Value {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+impl Encode for Value {
+    fn encode(
+        &self,
+        buf: &mut dyn Write,
+        default_decor: (&str, &str),
+        canonicalize: bool,
+        separator: Option<&str>,
+        layout: Layout,
+    ) -> Result {
         match self {
-            Value::Integer(repr) => write!(f, "{}", repr),
-            Value::String(repr) => write!(f, "{}", repr),
-            Value::Float(repr) => write!(f, "{}", repr),
-            Value::Boolean(repr) => write!(f, "{}", repr),
-            Value::DateTime(repr) => write!(f, "{}", repr),
-            Value::Array(array) => write!(f, "{}", array),
-            Value::InlineTable(table) => write!(f, "{}", table),
+            Value::Integer(repr) => repr.encode(buf, default_decor, canonicalize, separator, layout),
+            Value::String(repr) if canonicalize => {
+                repr.repr.encode_as(buf, default_decor, &escape_string(&repr.value), separator)
+            }
+            Value::String(repr) => repr.encode(buf, default_decor, canonicalize, separator, layout),
+            Value::Float(repr) if canonicalize => {
+                let text = canonical_float(&repr.repr.raw_value, repr.value);
+                repr.repr.encode_as(buf, default_decor, &text, separator)
+            }
+            Value::Float(repr) => repr.encode(buf, default_decor, canonicalize, separator, layout),
+            Value::Boolean(repr) => repr.encode(buf, default_decor, canonicalize, separator, layout),
+            Value::DateTime(repr) => repr.encode(buf, default_decor, canonicalize, separator, layout),
+            Value::Array(array) => array.encode(buf, default_decor, canonicalize, separator, layout),
+            Value::InlineTable(table) => table.encode(buf, default_decor, canonicalize, separator, layout),
         }
     }
 }
 
-impl Display for Array {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        let len = self.len().saturating_sub(1);
-        write!(f, "{}[", self.decor.prefix)?;
-        for (i, v) in self.iter().enumerate() {
-            if i > 0 {
-                write!(f, ",")?;
-            }
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result { self.encode(f, ("", ""), false, None, Layout::Preserve) }
+}
 
-            // The last values decor.suffix is \n so we can't just
-            // write a `,` after we write the value
-            let mut v2 = v.clone();
-            let val = if i == len && self.trailing_comma && self.newlines {
-                v2.decor_mut().suffix.insert(0, ',');
-                &v2
-            } else {
-                v
-            };
-
-            write!(f, "{}", val)?;
-        }
+impl Array {
+    /// Writes `[...]` and its elements per `layout`, without this array's
+    /// own prefix/suffix decor. Used both by [`Encode::encode`] (wrapped in
+    /// that decor) and by [`encode_value_content`], which deliberately
+    /// renders nested containers bare so Compact/Expanded don't reintroduce
+    /// the source's whitespace one level down.
+    fn encode_body(&self, buf: &mut dyn Write, canonicalize: bool, layout: Layout) -> Result {
+        match layout {
+            Layout::Preserve => {
+                let last = self.len().saturating_sub(1);
+                write!(buf, "[")?;
+                for (i, v) in self.iter().enumerate() {
+                    if i > 0 {
+                        write!(buf, ",")?;
+                    }
+
+                    // Inject the trailing comma as the last element's own
+                    // separator instead of cloning it just to splice a `,`
+                    // into its suffix.
+                    let elem_separator = if i == last && self.trailing_comma && self.newlines {
+                        Some(",")
+                    } else {
+                        None
+                    };
+                    v.encode(buf, ("", ""), canonicalize, elem_separator, Layout::Preserve)?;
+                }
 
-        if self.trailing_comma && !self.newlines {
-            write!(f, ",")?;
+                if self.trailing_comma && !self.newlines {
+                    write!(buf, ",")?;
+                }
+
+                write!(buf, "{}", self.trailing)?;
+                write!(buf, "]")
+            }
+            Layout::Compact => {
+                write!(buf, "[")?;
+                for (i, v) in self.iter().enumerate() {
+                    if i > 0 {
+                        write!(buf, ", ")?;
+                    }
+                    encode_value_content(v, buf, canonicalize, layout)?;
+                }
+                write!(buf, "]")
+            }
+            Layout::Expanded { indent } => {
+                let pad = " ".repeat(indent);
+                write!(buf, "[")?;
+                for v in self.iter() {
+                    write!(buf, "\n{}", pad)?;
+                    encode_value_content(v, buf, canonicalize, layout)?;
+                    write!(buf, ",")?;
+                }
+                write!(buf, "\n]")
+            }
         }
+    }
+}
 
-        write!(f, "{}", self.trailing)?;
-        write!(f, "]{}", self.decor.suffix)
+impl Encode for Array {
+    fn encode(
+        &self,
+        buf: &mut dyn Write,
+        default_decor: (&str, &str),
+        canonicalize: bool,
+        separator: Option<&str>,
+        layout: Layout,
+    ) -> Result {
+        write!(buf, "{}", decor_or(&self.decor.prefix, default_decor.0))?;
+        self.encode_body(buf, canonicalize, layout)?;
+        if let Some(sep) = separator {
+            write!(buf, "{}", sep)?;
+        }
+        write!(buf, "{}", decor_or(&self.decor.suffix, default_decor.1))
     }
 }
 
-impl Display for InlineTable {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "{}{{", self.decor.prefix)?;
-        write!(f, "{}", self.preamble)?;
-        for (i, (key, value)) in self
-            .items
-            .iter()
-            .filter(|&(_, kv)| kv.value.is_value())
-            .map(|(_, kv)| (&kv.key, kv.value.as_value().unwrap()))
-            .enumerate()
-        {
-            if i > 0 {
-                write!(f, ",")?;
+impl Display for Array {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result { self.encode(f, ("", ""), false, None, Layout::Preserve) }
+}
+
+impl InlineTable {
+    /// Writes `{...}` and its entries per `layout`, without this table's own
+    /// prefix/suffix decor. See [`Array::encode_body`] for why nested
+    /// containers render bare under Compact/Expanded.
+    fn encode_body(&self, buf: &mut dyn Write, canonicalize: bool, layout: Layout) -> Result {
+        let pairs = || {
+            self.items
+                .iter()
+                .filter(|&(_, kv)| kv.value.is_value())
+                .map(|(_, kv)| (&kv.key, kv.value.as_value().unwrap()))
+        };
+
+        match layout {
+            Layout::Preserve => {
+                write!(buf, "{{")?;
+                write!(buf, "{}", self.preamble)?;
+                for (i, (key, value)) in pairs().enumerate() {
+                    if i > 0 {
+                        write!(buf, ",")?;
+                    }
+                    key.encode(buf, ("", ""), canonicalize, None, Layout::Preserve)?;
+                    write!(buf, "=")?;
+                    value.encode(buf, ("", ""), canonicalize, None, Layout::Preserve)?;
+                }
+                write!(buf, "}}")
+            }
+            Layout::Compact => {
+                let entries: Vec<_> = pairs().collect();
+                write!(buf, "{{")?;
+                write!(buf, "{}", self.preamble)?;
+                if !entries.is_empty() {
+                    write!(buf, " ")?;
+                }
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        write!(buf, ", ")?;
+                    }
+                    write!(buf, "{} = ", key.raw_value)?;
+                    encode_value_content(value, buf, canonicalize, layout)?;
+                }
+                if !entries.is_empty() {
+                    write!(buf, " ")?;
+                }
+                write!(buf, "}}")
+            }
+            Layout::Expanded { indent } => {
+                let pad = " ".repeat(indent);
+                write!(buf, "{{")?;
+                write!(buf, "{}", self.preamble)?;
+                for (key, value) in pairs() {
+                    write!(buf, "\n{}", pad)?;
+                    write!(buf, "{} = ", key.raw_value)?;
+                    encode_value_content(value, buf, canonicalize, layout)?;
+                    write!(buf, ",")?;
+                }
+                write!(buf, "\n}}")
             }
-            write!(f, "{}={}", key, value)?;
         }
-        write!(f, "}}{}", self.decor.suffix)
     }
 }
 
+impl Encode for InlineTable {
+    fn encode(
+        &self,
+        buf: &mut dyn Write,
+        default_decor: (&str, &str),
+        canonicalize: bool,
+        separator: Option<&str>,
+        layout: Layout,
+    ) -> Result {
+        write!(buf, "{}", decor_or(&self.decor.prefix, default_decor.0))?;
+        self.encode_body(buf, canonicalize, layout)?;
+        if let Some(sep) = separator {
+            write!(buf, "{}", sep)?;
+        }
+        write!(buf, "{}", decor_or(&self.decor.suffix, default_decor.1))
+    }
+}
+
+impl Display for InlineTable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result { self.encode(f, ("", ""), false, None, Layout::Preserve) }
+}
+
 impl Table {
     fn visit_nested_tables<'t, F>(
         &'t self,
@@ -126,27 +419,45 @@ impl Table {
     }
 }
 
+/// Default decor for a table/array-of-tables header: a blank line before and
+/// after, used whenever the header itself wasn't captured with its own decor.
+const HEADER_DECOR: (&str, &str) = ("\n", "\n");
+/// Default decor for a key on the left-hand side of `=`: no indentation
+/// before it, a space between it and `=`.
+const KEY_DECOR: (&str, &str) = ("", " ");
+/// Default decor for a value on the right-hand side of `=`. The suffix
+/// default is empty, not `"\n"`: the structural newline that ends a
+/// key=value line is always written explicitly below, independent of
+/// whatever the value's own suffix decor holds (e.g. a trailing comment),
+/// so a captured suffix never swallows the line terminator.
+const VALUE_DECOR: (&str, &str) = ("", "");
+
 fn visit_table(
     f: &mut dyn Write,
     table: &Table,
     path: &[&str],
     is_array_of_tables: bool,
+    canonicalize: bool,
+    layout: Layout,
 ) -> Result {
     if path.is_empty() {
         // don't print header for the root node
     } else if is_array_of_tables {
-        write!(f, "{}[[", table.decor.prefix)?;
+        write!(f, "{}[[", decor_or(&table.decor.prefix, HEADER_DECOR.0))?;
         write!(f, "{}", path.join("."))?;
-        writeln!(f, "]]{}", table.decor.suffix)?;
+        writeln!(f, "]]{}", decor_or(&table.decor.suffix, HEADER_DECOR.1))?;
     } else if !(table.implicit && table.values_len() == 0) {
-        write!(f, "{}[", table.decor.prefix)?;
+        write!(f, "{}[", decor_or(&table.decor.prefix, HEADER_DECOR.0))?;
         write!(f, "{}", path.join("."))?;
-        writeln!(f, "]{}", table.decor.suffix)?;
+        writeln!(f, "]{}", decor_or(&table.decor.suffix, HEADER_DECOR.1))?;
     }
     // print table body
     for kv in table.items.values() {
         if let Item::Value(ref value) = kv.value {
-            writeln!(f, "{}={}", kv.key, value)?;
+            kv.key.encode(f, KEY_DECOR, false, None, Layout::Preserve)?;
+            write!(f, "=")?;
+            value.encode(f, VALUE_DECOR, canonicalize, None, layout)?;
+            writeln!(f)?;
         }
     }
     Ok(())
@@ -157,7 +468,7 @@ impl Display for Table {
         let mut path = Vec::new();
 
         self.visit_nested_tables(&mut path, false, &mut |t, path, is_array| {
-            visit_table(f, t, path, is_array)
+            visit_table(f, t, path, is_array, false, Layout::Preserve)
         })?;
         Ok(())
     }
@@ -167,6 +478,25 @@ impl Document {
     /// Returns a string representation of the TOML document, attempting to keep
     /// the table headers in their original order.
     pub fn to_string_in_original_order(&self) -> String {
+        self.to_string_in_original_order_impl(false, Layout::Preserve)
+    }
+
+    /// Like [`to_string_in_original_order`](Self::to_string_in_original_order),
+    /// but also normalizes value reprs instead of echoing them verbatim:
+    /// strings are re-escaped, and whole-number floats whose repr has no `.`,
+    /// exponent, `inf` or `nan` gain a trailing `.0`.
+    pub fn to_string_canonical(&self) -> String {
+        self.to_string_in_original_order_impl(true, Layout::Preserve)
+    }
+
+    /// Like [`to_string_in_original_order`](Self::to_string_in_original_order),
+    /// but also imposes `layout` on every array and inline table instead of
+    /// reproducing whatever layout the source document used.
+    pub fn to_string_with_layout(&self, canonicalize: bool, layout: Layout) -> String {
+        self.to_string_in_original_order_impl(canonicalize, layout)
+    }
+
+    fn to_string_in_original_order_impl(&self, canonicalize: bool, layout: Layout) -> String {
         let mut string = String::new();
         let mut path = Vec::new();
         let mut last_position = 0;
@@ -177,7 +507,7 @@ impl Document {
                     last_position = pos;
                 }
                 let mut s = String::new();
-                visit_table(&mut s, t, p, is_array)?;
+                visit_table(&mut s, t, p, is_array, canonicalize, layout)?;
                 tables.push((last_position, s));
                 Ok(())
             })
@@ -198,3 +528,129 @@ impl Display for Document {
         write!(f, "{}", self.trailing)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Document {
+        input.parse().expect("valid TOML")
+    }
+
+    // A value's trailing inline comment must not swallow the newline that
+    // ends its key=value line, or the next line gets merged into the
+    // comment on re-parse.
+    #[test]
+    fn value_with_trailing_comment_keeps_line_terminator() {
+        let doc = parse("a = 1 # pinned\nb = 2\n");
+        assert_eq!(doc.to_string_in_original_order(), "a = 1 # pinned\nb = 2\n");
+    }
+
+    #[test]
+    fn canonical_reescapes_strings() {
+        let doc = parse("a = 'hello\tworld'\n");
+        assert_eq!(doc.to_string_canonical(), "a = \"hello\\tworld\"\n");
+    }
+
+    #[test]
+    fn canonical_escapes_del() {
+        // U+007F (DEL) is a control character and TOML basic strings require
+        // it to be escaped just like the ones below U+0020.
+        let doc = parse("a = \"\\u007F\"\n");
+        assert_eq!(doc.to_string_canonical(), "a = \"\\u007F\"\n");
+    }
+
+    #[test]
+    fn canonical_leaves_already_float_looking_reprs_alone() {
+        // TOML float grammar always requires a `.`, exponent, or inf/nan
+        // marker, so canonical_float's `.0`-appending branch is purely
+        // defensive -- these already look like floats and must round-trip
+        // unchanged.
+        let doc = parse("a = 1.0\nb = 1e0\n");
+        assert_eq!(doc.to_string_canonical(), "a = 1.0\nb = 1e0\n");
+    }
+
+    #[test]
+    fn canonical_leaves_integers_as_integers() {
+        let doc = parse("a = 1\n");
+        assert_eq!(doc.to_string_canonical(), "a = 1\n");
+    }
+
+    #[test]
+    fn multiline_array_with_trailing_comma_round_trips() {
+        let input = "a = [\n    1,\n    2,\n]\n";
+        let doc = parse(input);
+        assert_eq!(doc.to_string_in_original_order(), input);
+    }
+
+    #[test]
+    fn multiline_array_without_trailing_comma_round_trips() {
+        let input = "a = [\n    1,\n    2\n]\n";
+        let doc = parse(input);
+        assert_eq!(doc.to_string_in_original_order(), input);
+    }
+
+    #[test]
+    fn layout_preserve_keeps_source_formatting() {
+        let input = "a = [1,2,3]\n";
+        let doc = parse(input);
+        assert_eq!(doc.to_string_with_layout(false, Layout::Preserve), input);
+    }
+
+    #[test]
+    fn layout_compact_forces_single_line_array() {
+        let doc = parse("a = [\n    1,\n    2,\n    3,\n]\n");
+        assert_eq!(
+            doc.to_string_with_layout(false, Layout::Compact),
+            "a = [1, 2, 3]\n"
+        );
+    }
+
+    #[test]
+    fn layout_expanded_puts_one_element_per_line() {
+        let doc = parse("a = [1, 2, 3]\n");
+        assert_eq!(
+            doc.to_string_with_layout(false, Layout::Expanded { indent: 4 }),
+            "a = [\n    1,\n    2,\n    3,\n]\n"
+        );
+    }
+
+    #[test]
+    fn layout_compact_spaces_inline_table_braces() {
+        let doc = parse("a = {x=1,y=2}\n");
+        assert_eq!(
+            doc.to_string_with_layout(false, Layout::Compact),
+            "a = { x = 1, y = 2 }\n"
+        );
+    }
+
+    #[test]
+    fn layout_expanded_puts_one_inline_table_entry_per_line() {
+        let doc = parse("a = { x = 1, y = 2 }\n");
+        assert_eq!(
+            doc.to_string_with_layout(false, Layout::Expanded { indent: 2 }),
+            "a = {\n  x = 1,\n  y = 2,\n}\n"
+        );
+    }
+
+    #[test]
+    fn layout_compact_propagates_into_nested_containers() {
+        // Mirrors a real Cargo.toml dependency table: the nested `features`
+        // array must also be forced single-line, not left as the source
+        // wrote it.
+        let doc = parse("dep = { version = \"1\", features = [\n    \"a\",\n    \"b\",\n] }\n");
+        assert_eq!(
+            doc.to_string_with_layout(false, Layout::Compact),
+            "dep = { version = \"1\", features = [\"a\", \"b\"] }\n"
+        );
+    }
+
+    #[test]
+    fn layout_expanded_propagates_into_nested_containers() {
+        let doc = parse("dep = { version = \"1\", features = [\"a\", \"b\"] }\n");
+        assert_eq!(
+            doc.to_string_with_layout(false, Layout::Expanded { indent: 2 }),
+            "dep = {\n  version = \"1\",\n  features = [\n  \"a\",\n  \"b\",\n],\n}\n"
+        );
+    }
+}